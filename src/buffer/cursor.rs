@@ -1,6 +1,7 @@
 use std::ops::Deref;
 use std::rc::Rc;
 use std::cell::RefCell;
+use unicode_segmentation::UnicodeSegmentation;
 use buffer::{Position, GapBuffer};
 
 /// Read-only wrapper for a `Position`, to allow field level access to a
@@ -11,6 +12,7 @@ pub struct Cursor {
     pub data: Rc<RefCell<GapBuffer>>,
     pub position: Position,
     sticky_offset: usize,
+    anchor: Option<Position>,
 }
 
 impl Deref for Cursor {
@@ -21,6 +23,28 @@ impl Deref for Cursor {
     }
 }
 
+/// The character classes that word motions step between; a word boundary
+/// is any transition from one category to another.
+#[derive(PartialEq)]
+enum CharCategory {
+    Whitespace,
+    Word,
+    Punctuation,
+}
+
+/// Classifies a character for the purposes of word-wise motion. Word
+/// characters are alphanumerics plus the underscore, matching the usual
+/// editor convention.
+fn category(character: char) -> CharCategory {
+    if character.is_whitespace() {
+        CharCategory::Whitespace
+    } else if character.is_alphanumeric() || character == '_' {
+        CharCategory::Word
+    } else {
+        CharCategory::Punctuation
+    }
+}
+
 pub fn new(data: Rc<RefCell<GapBuffer>>, line: usize, offset: usize) -> Cursor {
     Cursor{
         data: data,
@@ -28,7 +52,8 @@ pub fn new(data: Rc<RefCell<GapBuffer>>, line: usize, offset: usize) -> Cursor {
             line: line,
             offset: offset
         },
-        sticky_offset: offset
+        sticky_offset: offset,
+        anchor: None
     }
 }
 
@@ -115,20 +140,47 @@ impl Cursor {
         }
     }
 
-    /// Decrements the cursor offset. The location is bounds-checked against
-    /// the data and the cursor will not be updated if it is out-of-bounds.
+    /// Moves the cursor to the previous grapheme boundary on the current
+    /// line. The `offset` stays a byte offset, but only ever rests on a
+    /// grapheme cluster boundary so multi-byte characters are stepped over
+    /// as a unit. The location is bounds-checked against the data.
     pub fn move_left(&mut self) {
         // Don't bother if we are already at the left edge.
         if self.offset == 0 { return; }
 
-        let new_position = Position{ line: self.line, offset: self.offset-1 };
+        let data = self.data.borrow().to_string();
+        let target_offset = match data.lines().nth(self.line) {
+            Some(line) => {
+                line.grapheme_indices(true)
+                    .map(|(index, _)| index)
+                    .take_while(|&index| index < self.offset)
+                    .last()
+                    .unwrap_or(0)
+            },
+            None => return,
+        };
+
+        let new_position = Position{ line: self.line, offset: target_offset };
         self.move_to(new_position);
     }
 
-    /// Increments the cursor offset. The location is bounds-checked against
-    /// the data and the cursor will not be updated if it is out-of-bounds.
+    /// Moves the cursor to the next grapheme boundary on the current line.
+    /// The `offset` stays a byte offset, but only ever rests on a grapheme
+    /// cluster boundary so multi-byte characters are stepped over as a unit.
+    /// The location is bounds-checked against the data.
     pub fn move_right(&mut self) {
-        let new_position = Position{ line: self.line, offset: self.offset+1 };
+        let data = self.data.borrow().to_string();
+        let target_offset = match data.lines().nth(self.line) {
+            Some(line) => {
+                line.grapheme_indices(true)
+                    .map(|(index, _)| index)
+                    .find(|&index| index > self.offset)
+                    .unwrap_or(line.len())
+            },
+            None => return,
+        };
+
+        let new_position = Position{ line: self.line, offset: target_offset };
         self.move_to(new_position);
     }
 
@@ -144,12 +196,128 @@ impl Cursor {
         let current_line = data.lines().nth(self.line);
         match current_line {
             Some(line) => {
-                let new_position = Position{ line: self.line, offset: line.len() };
+                // Use the end of the last grapheme cluster, which for any
+                // line is its byte length but is guaranteed to sit on a
+                // boundary rather than mid-character.
+                let offset = line.grapheme_indices(true)
+                    .last()
+                    .map(|(index, grapheme)| index + grapheme.len())
+                    .unwrap_or(0);
+                let new_position = Position{ line: self.line, offset: offset };
                 self.move_to(new_position);
             },
             None => (),
         }
     }
+
+    /// Moves the cursor forward to the start of the next word, skipping
+    /// over the current token and any trailing whitespace. A word boundary
+    /// is any transition between character classes (whitespace, word, or
+    /// punctuation), so runs of punctuation count as their own token. The
+    /// motion wraps onto the next line when the end of the current one is
+    /// reached and is a no-op at the end of the buffer.
+    pub fn move_to_next_word(&mut self) {
+        let characters = self.characters();
+        let index = match characters.iter().position(|&(position, _)| position == self.position) {
+            Some(index) => index,
+            None => return,
+        };
+
+        let mut target = index;
+
+        // Step past the token the cursor currently sits on.
+        let current_class = category(characters[target].1);
+        if current_class != CharCategory::Whitespace {
+            while target < characters.len() && category(characters[target].1) == current_class {
+                target += 1;
+            }
+        }
+
+        // Step past the whitespace separating it from the next token.
+        while target < characters.len() && category(characters[target].1) == CharCategory::Whitespace {
+            target += 1;
+        }
+
+        if target < characters.len() {
+            self.move_to(characters[target].0);
+        }
+    }
+
+    /// Moves the cursor backward to the start of the previous word, the
+    /// mirror image of `move_to_next_word`. A word boundary is any
+    /// transition between character classes (whitespace, word, or
+    /// punctuation). The motion wraps onto the previous line and is a no-op
+    /// at the start of the buffer.
+    pub fn move_to_previous_word(&mut self) {
+        let characters = self.characters();
+        let index = match characters.iter().position(|&(position, _)| position == self.position) {
+            Some(index) => index,
+            None => return,
+        };
+
+        // Already at the start of the buffer; nowhere to go.
+        if index == 0 { return; }
+
+        // Step back over any whitespace preceding the cursor.
+        let mut target = index - 1;
+        while target > 0 && category(characters[target].1) == CharCategory::Whitespace {
+            target -= 1;
+        }
+
+        // Rewind to the start of the token we landed in.
+        let current_class = category(characters[target].1);
+        while target > 0 && category(characters[target - 1].1) == current_class {
+            target -= 1;
+        }
+
+        self.move_to(characters[target].0);
+    }
+
+    /// Builds the ordered sequence of `(Position, char)` pairs for the whole
+    /// buffer, appending a synthetic newline at each end-of-line so that
+    /// word motions treat line breaks as whitespace and wrap across lines.
+    fn characters(&self) -> Vec<(Position, char)> {
+        let data = self.data.borrow().to_string();
+        let mut characters = Vec::new();
+        for (line_number, line) in data.lines().enumerate() {
+            for (offset, character) in line.char_indices() {
+                characters.push((Position{ line: line_number, offset: offset }, character));
+            }
+            characters.push((Position{ line: line_number, offset: line.len() }, '\n'));
+        }
+        characters
+    }
+
+    /// Anchors a selection at the cursor's current position. Subsequent
+    /// `move_*` calls leave the anchor fixed while the head (the cursor
+    /// position) moves, extending a selection between the two endpoints.
+    pub fn set_anchor(&mut self) {
+        self.anchor = Some(self.position);
+    }
+
+    /// Collapses any active selection by discarding the anchor; the cursor
+    /// position is left untouched.
+    pub fn clear_anchor(&mut self) {
+        self.anchor = None;
+    }
+
+    /// Whether an anchor is currently set, i.e. the cursor represents a
+    /// range rather than a single point.
+    pub fn has_selection(&self) -> bool {
+        self.anchor.is_some()
+    }
+
+    /// Returns the selected range as an ordered `(start, end)` pair, where
+    /// `start <= end`, or `None` if there is no active selection.
+    pub fn selected_range(&self) -> Option<(Position, Position)> {
+        self.anchor.map(|anchor| {
+            if anchor <= self.position {
+                (anchor, self.position)
+            } else {
+                (self.position, anchor)
+            }
+        })
+    }
 }
 
 #[cfg(test)]
@@ -249,4 +417,138 @@ mod tests {
         assert_eq!(cursor.line, 0);
         assert_eq!(cursor.offset, 0);
     }
+
+    #[test]
+    fn move_to_next_word_lands_on_the_start_of_the_following_word() {
+        let buffer = Rc::new(RefCell::new(gap_buffer::new("foo bar baz".to_string())));
+        let mut cursor = new(buffer, 0, 0);
+        cursor.move_to_next_word();
+        assert_eq!(cursor.line, 0);
+        assert_eq!(cursor.offset, 4);
+    }
+
+    #[test]
+    fn move_to_next_word_treats_punctuation_as_its_own_token() {
+        let buffer = Rc::new(RefCell::new(gap_buffer::new("foo.bar".to_string())));
+        let mut cursor = new(buffer, 0, 0);
+        cursor.move_to_next_word();
+        assert_eq!(cursor.offset, 3);
+    }
+
+    #[test]
+    fn move_to_next_word_wraps_to_the_following_line() {
+        let buffer = Rc::new(RefCell::new(gap_buffer::new("foo\nbar".to_string())));
+        let mut cursor = new(buffer, 0, 0);
+        cursor.move_to_next_word();
+        assert_eq!(cursor.line, 1);
+        assert_eq!(cursor.offset, 0);
+    }
+
+    #[test]
+    fn move_to_previous_word_lands_on_the_start_of_the_preceding_word() {
+        let buffer = Rc::new(RefCell::new(gap_buffer::new("foo bar baz".to_string())));
+        let mut cursor = new(buffer, 0, 8);
+        cursor.move_to_previous_word();
+        assert_eq!(cursor.offset, 4);
+    }
+
+    #[test]
+    fn move_to_previous_word_wraps_to_the_preceding_line() {
+        let buffer = Rc::new(RefCell::new(gap_buffer::new("foo\nbar".to_string())));
+        let mut cursor = new(buffer, 1, 0);
+        cursor.move_to_previous_word();
+        assert_eq!(cursor.line, 0);
+        assert_eq!(cursor.offset, 0);
+    }
+
+    #[test]
+    fn new_cursor_has_no_selection() {
+        let buffer = Rc::new(RefCell::new(gap_buffer::new("This is a test.".to_string())));
+        let cursor = new(buffer, 0, 5);
+        assert_eq!(cursor.has_selection(), false);
+        assert_eq!(cursor.selected_range(), None);
+    }
+
+    #[test]
+    fn set_anchor_keeps_the_anchor_fixed_as_the_head_moves() {
+        let buffer = Rc::new(RefCell::new(gap_buffer::new("This is a test.".to_string())));
+        let mut cursor = new(buffer, 0, 5);
+        cursor.set_anchor();
+        cursor.move_right();
+        cursor.move_right();
+        assert!(cursor.has_selection());
+        assert_eq!(
+            cursor.selected_range(),
+            Some((Position{ line: 0, offset: 5 }, Position{ line: 0, offset: 7 }))
+        );
+    }
+
+    #[test]
+    fn selected_range_orders_its_endpoints() {
+        let buffer = Rc::new(RefCell::new(gap_buffer::new("This is a test.".to_string())));
+        let mut cursor = new(buffer, 0, 7);
+        cursor.set_anchor();
+        cursor.move_left();
+        cursor.move_left();
+        assert_eq!(
+            cursor.selected_range(),
+            Some((Position{ line: 0, offset: 5 }, Position{ line: 0, offset: 7 }))
+        );
+    }
+
+    #[test]
+    fn move_right_steps_over_a_multi_byte_character() {
+        // "é" is two bytes, so a byte-based move would land mid-character.
+        let buffer = Rc::new(RefCell::new(gap_buffer::new("é.".to_string())));
+        let mut cursor = new(buffer, 0, 0);
+        cursor.move_right();
+        assert_eq!(cursor.offset, 2);
+    }
+
+    #[test]
+    fn move_left_steps_over_a_multi_byte_character() {
+        let buffer = Rc::new(RefCell::new(gap_buffer::new("é.".to_string())));
+        let mut cursor = new(buffer, 0, 2);
+        cursor.move_left();
+        assert_eq!(cursor.offset, 0);
+    }
+
+    #[test]
+    fn move_right_steps_over_a_zwj_emoji_sequence() {
+        // "👨‍👩‍👧" is a single ZWJ grapheme cluster spanning several
+        // code points; the cursor should clear it in one step.
+        let family = "👨‍👩‍👧";
+        let buffer = Rc::new(RefCell::new(gap_buffer::new(format!("{}.", family))));
+        let mut cursor = new(buffer, 0, 0);
+        cursor.move_right();
+        assert_eq!(cursor.offset, family.len());
+    }
+
+    #[test]
+    fn move_left_steps_over_a_zwj_emoji_sequence() {
+        let family = "👨‍👩‍👧";
+        let buffer = Rc::new(RefCell::new(gap_buffer::new(format!("{}.", family))));
+        let mut cursor = new(buffer, 0, family.len());
+        cursor.move_left();
+        assert_eq!(cursor.offset, 0);
+    }
+
+    #[test]
+    fn move_to_end_of_line_rests_on_a_grapheme_boundary() {
+        let family = "👨‍👩‍👧";
+        let buffer = Rc::new(RefCell::new(gap_buffer::new(family.to_string())));
+        let mut cursor = new(buffer, 0, 0);
+        cursor.move_to_end_of_line();
+        assert_eq!(cursor.offset, family.len());
+    }
+
+    #[test]
+    fn clear_anchor_collapses_the_selection() {
+        let buffer = Rc::new(RefCell::new(gap_buffer::new("This is a test.".to_string())));
+        let mut cursor = new(buffer, 0, 5);
+        cursor.set_anchor();
+        cursor.clear_anchor();
+        assert_eq!(cursor.has_selection(), false);
+        assert_eq!(cursor.selected_range(), None);
+    }
 }
@@ -1,4 +1,5 @@
 use std::cmp::{PartialOrd, Ordering};
+use std::ops::Range;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Position {
@@ -6,6 +7,114 @@ pub struct Position {
     pub offset: usize,
 }
 
+/// A precomputed index of line-start byte offsets for a buffer, used to
+/// convert between an absolute byte offset into the whole buffer and a
+/// `(line, offset)` `Position`. Building the index once and reusing it
+/// avoids the O(n) line scan otherwise needed for each conversion.
+pub struct LineIndex {
+    // The byte offset at which each line begins, in ascending order. The
+    // first entry is always 0, and a new entry follows every newline.
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    /// Builds an index from the buffer's contents by recording the byte
+    /// offset immediately following every newline.
+    pub fn new(data: &str) -> LineIndex {
+        let mut line_starts = vec![0];
+        for (offset, character) in data.char_indices() {
+            if character == '\n' {
+                line_starts.push(offset + 1);
+            }
+        }
+
+        LineIndex{ line_starts: line_starts }
+    }
+}
+
+impl Position {
+    /// Converts an absolute byte offset into the buffer to a `Position`
+    /// using the supplied index. The line is the last line-start less than
+    /// or equal to the offset, found by binary search, and the column is
+    /// the offset relative to that line-start.
+    pub fn from_offset(index: &LineIndex, offset: usize) -> Position {
+        let line = match index.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        };
+
+        Position{ line: line, offset: offset - index.line_starts[line] }
+    }
+
+    /// Converts this `Position` back to an absolute byte offset into the
+    /// buffer, the inverse of `from_offset`.
+    pub fn to_offset(&self, index: &LineIndex) -> usize {
+        index.line_starts[self.line] + self.offset
+    }
+}
+
+/// A single edit applied to a buffer: the byte range it replaces, together
+/// with the text substituted in its place. An insertion is an empty range,
+/// and a deletion an empty replacement.
+pub struct Edit {
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+/// Maps a pre-edit `Position` to its correct post-edit `Position` after a
+/// set of edits has been applied to `text`, so saved cursors, selection
+/// anchors, and bookmarks survive an edit instead of being invalidated.
+///
+/// The edits are walked in offset order while a running byte delta is
+/// tracked: offsets strictly before an edit shift by the accumulated delta,
+/// offsets inside a replaced range clamp to the edit's start, and offsets
+/// after an edit pick up its length change. The resulting offset is then
+/// resolved against the edited buffer so the `(line, offset)` coordinate
+/// reflects any newlines the replacements consumed or produced.
+pub fn translate(text: &str, position: Position, edits: &[Edit]) -> Position {
+    // Visit the edits left-to-right regardless of the order supplied.
+    let mut ordered: Vec<&Edit> = edits.iter().collect();
+    ordered.sort_by(|left, right| left.range.start.cmp(&right.range.start));
+
+    // Apply the edits to build the post-edit buffer, which we resolve the
+    // translated offset against at the end.
+    let mut edited = String::new();
+    let mut consumed = 0;
+    for edit in &ordered {
+        edited.push_str(&text[consumed..edit.range.start]);
+        edited.push_str(&edit.replacement);
+        consumed = edit.range.end;
+    }
+    edited.push_str(&text[consumed..]);
+
+    // Translate the absolute offset of the position through the delta.
+    let original_offset = position.to_offset(&LineIndex::new(text));
+    let mut delta: isize = 0;
+    let mut translated = original_offset as isize + delta;
+    for edit in &ordered {
+        let range_length = edit.range.end - edit.range.start;
+        if original_offset < edit.range.start {
+            // The position precedes this and every later edit.
+            translated = original_offset as isize + delta;
+            break;
+        } else if original_offset < edit.range.end {
+            // The position falls within the replaced range; clamp it to the
+            // edit's start so it doesn't point into deleted text.
+            translated = edit.range.start as isize + delta;
+            break;
+        } else {
+            // The position follows this edit; carry its length change.
+            delta += edit.replacement.len() as isize - range_length as isize;
+            translated = original_offset as isize + delta;
+        }
+    }
+
+    let new_offset = if translated < 0 { 0 } else { translated as usize };
+    let new_offset = if new_offset > edited.len() { edited.len() } else { new_offset };
+
+    Position::from_offset(&LineIndex::new(&edited), new_offset)
+}
+
 impl PartialOrd for Position {
     fn partial_cmp(&self, other: &Position) -> Option<Ordering> {
         Some(
@@ -28,7 +137,72 @@ impl PartialOrd for Position {
 
 #[cfg(test)]
 mod tests {
-    use super::Position;
+    use super::{Position, LineIndex, Edit, translate};
+
+    #[test]
+    fn translate_shifts_a_position_after_an_insertion() {
+        let edits = vec![Edit{ range: 0..0, replacement: "big ".to_string() }];
+        let translated = translate("hello world", Position{ line: 0, offset: 6 }, &edits);
+        assert_eq!(translated, Position{ line: 0, offset: 10 });
+    }
+
+    #[test]
+    fn translate_leaves_a_position_before_an_edit_unchanged() {
+        let edits = vec![Edit{ range: 6..11, replacement: "".to_string() }];
+        let translated = translate("hello world", Position{ line: 0, offset: 2 }, &edits);
+        assert_eq!(translated, Position{ line: 0, offset: 2 });
+    }
+
+    #[test]
+    fn translate_clamps_a_position_inside_a_deleted_range_to_its_start() {
+        let edits = vec![Edit{ range: 6..11, replacement: "".to_string() }];
+        let translated = translate("hello world", Position{ line: 0, offset: 8 }, &edits);
+        assert_eq!(translated, Position{ line: 0, offset: 6 });
+    }
+
+    #[test]
+    fn translate_accumulates_deltas_across_multiple_edits() {
+        let edits = vec![
+            Edit{ range: 0..0, replacement: "A".to_string() },
+            Edit{ range: 2..2, replacement: "B".to_string() },
+        ];
+        let translated = translate("hello", Position{ line: 0, offset: 5 }, &edits);
+        assert_eq!(translated, Position{ line: 0, offset: 7 });
+    }
+
+    #[test]
+    fn translate_moves_a_position_onto_a_produced_line() {
+        let edits = vec![Edit{ range: 0..0, replacement: "x\ny".to_string() }];
+        let translated = translate("ab", Position{ line: 0, offset: 2 }, &edits);
+        assert_eq!(translated, Position{ line: 1, offset: 3 });
+    }
+
+    #[test]
+    fn from_offset_maps_an_offset_to_its_line_and_column() {
+        let index = LineIndex::new("first\nsecond\nthird");
+        assert_eq!(Position::from_offset(&index, 0), Position{ line: 0, offset: 0 });
+        assert_eq!(Position::from_offset(&index, 3), Position{ line: 0, offset: 3 });
+        // Offset 6 is the 's' at the start of the second line.
+        assert_eq!(Position::from_offset(&index, 6), Position{ line: 1, offset: 0 });
+        assert_eq!(Position::from_offset(&index, 9), Position{ line: 1, offset: 3 });
+    }
+
+    #[test]
+    fn to_offset_maps_a_position_back_to_an_absolute_offset() {
+        let index = LineIndex::new("first\nsecond\nthird");
+        assert_eq!(Position{ line: 0, offset: 3 }.to_offset(&index), 3);
+        assert_eq!(Position{ line: 1, offset: 0 }.to_offset(&index), 6);
+        assert_eq!(Position{ line: 2, offset: 2 }.to_offset(&index), 15);
+    }
+
+    #[test]
+    fn from_offset_and_to_offset_round_trip() {
+        let index = LineIndex::new("first\nsecond\nthird");
+        for offset in 0..18 {
+            let position = Position::from_offset(&index, offset);
+            assert_eq!(position.to_offset(&index), offset);
+        }
+    }
 
     #[test]
     fn compare_works_when_lines_differ() {